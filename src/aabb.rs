@@ -0,0 +1,100 @@
+//! Axis-aligned bounding boxes.
+
+use crate::axis::Axis;
+use crate::{Point3, Vector3};
+
+/// An axis-aligned bounding box, represented by its minimum and maximum corner.
+///
+/// With the `bytemuck` feature enabled, this is `#[repr(C)]` and derives `Pod`/
+/// `Zeroable` (its fields, [`Point3`], already satisfy those bounds), so that
+/// [`FlatNode`], which embeds an `AABB`, is itself GPU-uploadable.
+///
+/// [`Point3`]: ../struct.Point3.html
+/// [`FlatNode`]: ../flat_bvh/struct.FlatNode.html
+///
+#[cfg_attr(feature = "bytemuck", repr(C))]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AABB {
+    /// The minimum corner of the box.
+    pub min: Point3,
+    /// The maximum corner of the box.
+    pub max: Point3,
+}
+
+impl AABB {
+    /// Creates an `AABB` with the given bounds.
+    pub fn with_bounds(min: Point3, max: Point3) -> AABB {
+        AABB { min, max }
+    }
+
+    /// Creates an empty `AABB`, i.e. one for which [`AABB::join`] and [`AABB::grow`] will
+    /// always return the other operand untouched.
+    ///
+    /// [`AABB::join`]: struct.AABB.html#method.join
+    /// [`AABB::grow`]: struct.AABB.html#method.grow
+    ///
+    pub fn empty() -> AABB {
+        AABB {
+            min: Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: Point3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    /// Returns a new `AABB` which contains both `self` and `other`.
+    pub fn join(&self, other: &AABB) -> AABB {
+        AABB::with_bounds(self.min.min(&other.min), self.max.max(&other.max))
+    }
+
+    /// Returns a new `AABB` which contains `self` and the point `p`.
+    pub fn grow(&self, p: &Point3) -> AABB {
+        AABB::with_bounds(self.min.min(p), self.max.max(p))
+    }
+
+    /// Returns the size of the `AABB` along each axis.
+    pub fn size(&self) -> Vector3 {
+        self.max - self.min
+    }
+
+    /// Returns the center point of the `AABB`.
+    pub fn center(&self) -> Point3 {
+        self.min + self.size() * 0.5
+    }
+
+    /// Returns the surface area of the `AABB`.
+    pub fn surface_area(&self) -> f32 {
+        let size = self.size();
+        2.0 * (size.x * size.y + size.x * size.z + size.y * size.z)
+    }
+
+    /// Returns the axis along which the `AABB` has its largest extent.
+    pub fn max_extent_axis(&self) -> Axis {
+        let size = self.size();
+        if size.x > size.y && size.x > size.z {
+            Axis::X
+        } else if size.y > size.z {
+            Axis::Y
+        } else {
+            Axis::Z
+        }
+    }
+
+    /// Returns `false` if either corner has a NaN or infinite coordinate. A non-finite
+    /// `AABB` would silently corrupt centroid sorting during a [`BVH`] build, so builders
+    /// exclude shapes whose bounds fail this check instead of folding them in.
+    ///
+    /// [`BVH`]: ../bvh/struct.BVH.html
+    ///
+    pub fn is_finite(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite()
+    }
+}
+
+/// A trait implemented by anything that can be bounded by an [`AABB`].
+///
+/// [`AABB`]: struct.AABB.html
+///
+pub trait Bounded {
+    /// Returns the geometric bounds of this object.
+    fn aabb(&self) -> AABB;
+}
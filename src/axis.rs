@@ -0,0 +1,14 @@
+//! Helper module for indexing points/vectors by a chosen coordinate axis, used when a
+//! split plane or ray-box test needs to work generically across x/y/z.
+
+/// An `Axis` is used to find out how to sort a list of nodes along a certain axis, or to
+/// index a `Point3`/`Vector3` by that axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// Index of the x-axis.
+    X = 0,
+    /// Index of the y-axis.
+    Y = 1,
+    /// Index of the z-axis.
+    Z = 2,
+}
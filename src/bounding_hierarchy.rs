@@ -0,0 +1,45 @@
+//! This module defines the `BoundingHierarchy` trait, which is the common interface of
+//! the various bounding hierarchies this crate provides (currently [`BVH`] and
+//! [`FlatBVH`]), and `BHShape`, which is implemented by any shape which can be stored in
+//! one of them.
+//!
+//! [`BVH`]: ../bvh/struct.BVH.html
+//! [`FlatBVH`]: ../flat_bvh/type.FlatBVH.html
+
+use crate::aabb::Bounded;
+use crate::ray::Ray;
+
+/// Any shape which can be stored in a [`BoundingHierarchy`] needs to implement this trait
+/// in addition to [`Bounded`]. It allows the shape to remember at which node of the
+/// hierarchy it is stored; the builders set it on every shape they place in a leaf.
+///
+/// [`BoundingHierarchy`]: trait.BoundingHierarchy.html
+/// [`Bounded`]: ../aabb/trait.Bounded.html
+///
+pub trait BHShape: Bounded {
+    /// Sets the index of the node in the hierarchy which stores this shape.
+    fn set_bh_node_index(&mut self, index: usize);
+
+    /// Returns the index of the node in the hierarchy which stores this shape.
+    fn bh_node_index(&self) -> usize;
+}
+
+/// This trait defines an object which is built by a [`Bounded`] slice of shapes and can
+/// be traversed to return a subset of these shapes. It is implemented by [`BVH`] and
+/// [`FlatBVH`].
+///
+/// [`Bounded`]: ../aabb/trait.Bounded.html
+/// [`BVH`]: ../bvh/struct.BVH.html
+/// [`FlatBVH`]: ../flat_bvh/type.FlatBVH.html
+///
+pub trait BoundingHierarchy {
+    /// Builds a bounding hierarchy over `shapes`.
+    fn build<T: BHShape>(shapes: &mut [T]) -> Self;
+
+    /// Traverses the hierarchy, returning a reference to every shape whose bounds the
+    /// given `ray` crosses.
+    fn traverse<'a, T: Bounded>(&'a self, ray: &Ray, shapes: &'a [T]) -> Vec<&'a T>;
+
+    /// Prints a textual representation of the hierarchy, for debugging purposes.
+    fn pretty_print(&self) {}
+}
@@ -0,0 +1,902 @@
+//! This module defines the `BVH` struct, the builders that construct it, and the
+//! recursive (non-flat) traversal used before a tree is handed to `flat_bvh`.
+
+use crate::aabb::{Bounded, AABB};
+use crate::bounding_hierarchy::{BHShape, BoundingHierarchy};
+use crate::ray::Ray;
+use crate::Point3;
+
+/// A node of a [`BVH`]. Each `Node` has exactly two children, and each `Leaf` refers to one
+/// or more shapes (more than one only when the tree was built by
+/// [`BVH::build_with_sah`] and its cost model preferred stopping over splitting further).
+///
+/// [`BVH`]: struct.BVH.html
+/// [`BVH::build_with_sah`]: struct.BVH.html#method.build_with_sah
+///
+#[derive(Debug, Clone)]
+pub enum BVHNode {
+    /// A leaf node, containing one or more shapes.
+    Leaf {
+        /// The index of this node's parent, within the owning `BVH`'s `nodes`.
+        parent_index: usize,
+        /// The depth of this node within the tree. The root has depth `0`.
+        depth: u32,
+        /// The index of the first shape stored at this leaf, within the owning `BVH`'s
+        /// `shape_indices`.
+        shape_index: usize,
+        /// The number of consecutive shapes (starting at `shape_index`, within the owning
+        /// `BVH`'s `shape_indices`) stored at this leaf.
+        shape_count: usize,
+    },
+    /// An interior node with exactly two children.
+    Node {
+        /// The index of this node's parent, within the owning `BVH`'s `nodes`.
+        parent_index: usize,
+        /// The depth of this node within the tree. The root has depth `0`.
+        depth: u32,
+        /// The bounds of the left subtree.
+        child_l_aabb: AABB,
+        /// The index of the left child, within the owning `BVH`'s `nodes`.
+        child_l_index: usize,
+        /// The bounds of the right subtree.
+        child_r_aabb: AABB,
+        /// The index of the right child, within the owning `BVH`'s `nodes`.
+        child_r_index: usize,
+    },
+}
+
+/// A bounding volume hierarchy, represented by a flat `Vec` of [`BVHNode`]s in which
+/// children are addressed by index rather than by pointer.
+///
+/// [`BVHNode`]: enum.BVHNode.html
+///
+#[allow(clippy::upper_case_acronyms)]
+pub struct BVH {
+    /// The tree's nodes. `nodes[0]` is the root, unless the tree is empty.
+    pub nodes: Vec<BVHNode>,
+    /// The permutation of (included) shape indices the builder settled on: every
+    /// [`BVHNode::Leaf`] refers to a contiguous run of this array via its `shape_index`/
+    /// `shape_count`, rather than indexing the shapes slice directly, so that a leaf can
+    /// cover more than one (not necessarily contiguous in the original slice) shape.
+    ///
+    /// [`BVHNode::Leaf`]: enum.BVHNode.html#variant.Leaf
+    ///
+    pub shape_indices: Vec<usize>,
+    /// Indices (into the shapes slice passed to the builder) of shapes whose `aabb()` had a
+    /// NaN or infinite extent and were therefore excluded from the tree. See
+    /// [`BVH::excluded_shapes`].
+    ///
+    /// [`BVH::excluded_shapes`]: struct.BVH.html#method.excluded_shapes
+    ///
+    excluded_shapes: Vec<usize>,
+}
+
+/// A triple of (original shape index, its AABB, its centroid), the unit of work a builder
+/// partitions as it descends the tree.
+type BuildItem = (usize, AABB, Point3);
+
+/// Splits `shapes` into build items and excluded indices: a shape whose `aabb()` is not
+/// [`AABB::is_finite`] would silently corrupt centroid sorting, so it is left out of
+/// `items` and its index recorded instead.
+///
+/// [`AABB::is_finite`]: ../aabb/struct.AABB.html#method.is_finite
+///
+fn collect_finite_items<T: Bounded>(shapes: &[T]) -> (Vec<BuildItem>, Vec<usize>) {
+    let mut items = Vec::with_capacity(shapes.len());
+    let mut excluded = Vec::new();
+    for (shape_index, shape) in shapes.iter().enumerate() {
+        let aabb = shape.aabb();
+        if aabb.is_finite() {
+            items.push((shape_index, aabb, aabb.center()));
+        } else {
+            excluded.push(shape_index);
+        }
+    }
+    (items, excluded)
+}
+
+fn node_count_for(num_items: usize) -> usize {
+    // A tree that always splits down to singleton leaves is a full binary tree: every
+    // internal node has exactly two children, so `num_items` leaves imply
+    // `num_items - 1` internal nodes.
+    if num_items == 0 {
+        0
+    } else {
+        2 * num_items - 1
+    }
+}
+
+fn placeholder_nodes(count: usize) -> Vec<BVHNode> {
+    (0..count)
+        .map(|_| BVHNode::Leaf {
+            parent_index: 0,
+            depth: 0,
+            shape_index: 0,
+            shape_count: 1,
+        })
+        .collect()
+}
+
+/// Splits `items` into two non-empty groups around the median centroid along `items`'s
+/// longest centroid-bounds axis, and returns that axis' sorted midpoint. This is the
+/// default split strategy used by [`BVH::build`].
+///
+/// [`BVH::build`]: struct.BVH.html#method.build
+///
+fn split_median(items: &mut [BuildItem]) -> usize {
+    let mut centroid_bounds = AABB::empty();
+    for (_, _, centroid) in items.iter() {
+        centroid_bounds = centroid_bounds.grow(centroid);
+    }
+    let axis = centroid_bounds.max_extent_axis();
+    items.sort_by(|a, b| a.2[axis].partial_cmp(&b.2[axis]).unwrap());
+    items.len() / 2
+}
+
+/// Configuration for [`BVH::build_with_sah`].
+///
+/// At each node, `build_with_sah` compares the best binned-SAH split cost against the cost
+/// of simply making a leaf of all of the node's shapes (`N·C_isect`), per Blender Cycles'
+/// `bvh_binning` cost formula: `C_trav + (A_L/A)·N_L·C_isect + (A_R/A)·N_R·C_isect` for a
+/// split, vs. `N·C_isect` for a leaf. Whichever is cheaper wins, subject to `max_leaf_size`:
+/// a node with more than `max_leaf_size` shapes is always split, regardless of cost.
+///
+/// [`BVH::build_with_sah`]: struct.BVH.html#method.build_with_sah
+///
+#[derive(Debug, Clone, Copy)]
+pub struct SahConfig {
+    /// The number of uniform bins primitive centroids are projected into along a node's
+    /// longest centroid-bounds axis. Blender's Cycles uses 16.
+    pub bin_count: usize,
+    /// The estimated relative cost of descending into a child node (`C_trav`).
+    pub traversal_cost: f32,
+    /// The estimated relative cost of testing a single shape for intersection (`C_isect`).
+    pub intersection_cost: f32,
+    /// The maximum number of shapes a single leaf may hold. A node with more shapes than
+    /// this is always split, even if the cost model would otherwise prefer a leaf.
+    pub max_leaf_size: usize,
+}
+
+impl Default for SahConfig {
+    /// The bin count and cost constants used by Blender Cycles' `bvh_binning`, with a
+    /// `max_leaf_size` of 4.
+    fn default() -> SahConfig {
+        SahConfig {
+            bin_count: 16,
+            traversal_cost: 1.0,
+            intersection_cost: 1.0,
+            max_leaf_size: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bin {
+    count: usize,
+    aabb: AABB,
+}
+
+impl Bin {
+    fn empty() -> Bin {
+        Bin {
+            count: 0,
+            aabb: AABB::empty(),
+        }
+    }
+
+    fn grow(&mut self, aabb: &AABB) {
+        self.count += 1;
+        self.aabb = self.aabb.join(aabb);
+    }
+}
+
+/// The outcome of [`sah_plan`]: either stop here and make a leaf of every item, or split at
+/// the given partition index (in the same sense as [`split_median`]'s return value, i.e. `items[..mid]` goes left and `items[mid..]` goes right, with `items` already
+/// reordered accordingly).
+///
+/// [`split_median`]: fn.split_median.html
+///
+enum SahPlan {
+    /// Make a single leaf covering every item.
+    Leaf,
+    /// Split at this partition index; `items` has already been reordered.
+    Split(usize),
+}
+
+/// Bins `items` by centroid along their longest centroid-bounds axis into
+/// `config.bin_count` uniform bins, sweeps the resulting `config.bin_count - 1` candidate
+/// split planes left-to-right and right-to-left to find the one with the lowest estimated
+/// SAH cost, and compares it against the cost of making a leaf of every item (see
+/// [`SahConfig`]). Returns [`SahPlan::Leaf`] if a leaf is cheaper (or no candidate plane
+/// leaves both sides non-empty) and `items.len() <= config.max_leaf_size`; otherwise splits
+/// at the cheapest plane, falling back to [`split_median`] when the centroids are
+/// degenerate (all equal along the chosen axis) or no plane leaves both sides non-empty.
+///
+/// [`SahConfig`]: struct.SahConfig.html
+/// [`split_median`]: fn.split_median.html
+///
+fn sah_plan(config: &SahConfig, items: &mut [BuildItem]) -> SahPlan {
+    let forced_split = items.len() > config.max_leaf_size;
+    let leaf_cost = items.len() as f32 * config.intersection_cost;
+
+    let bin_count = config.bin_count.max(2);
+
+    let mut node_aabb = AABB::empty();
+    let mut centroid_bounds = AABB::empty();
+    for (_, aabb, centroid) in items.iter() {
+        node_aabb = node_aabb.join(aabb);
+        centroid_bounds = centroid_bounds.grow(centroid);
+    }
+    let axis = centroid_bounds.max_extent_axis();
+    let axis_min = centroid_bounds.min[axis];
+    let axis_extent = centroid_bounds.max[axis] - axis_min;
+
+    if axis_extent <= 0.0 {
+        return if forced_split {
+            SahPlan::Split(split_median(items))
+        } else {
+            SahPlan::Leaf
+        };
+    }
+
+    let bin_of = |centroid: &Point3| -> usize {
+        let relative = (centroid[axis] - axis_min) / axis_extent;
+        ((relative * bin_count as f32) as usize).min(bin_count - 1)
+    };
+
+    let mut bins = vec![Bin::empty(); bin_count];
+    for (_, aabb, centroid) in items.iter() {
+        bins[bin_of(centroid)].grow(aabb);
+    }
+
+    // Prefix sweep: `left[i]` covers bins `0..=i`.
+    let mut left_count = vec![0usize; bin_count];
+    let mut left_aabb = vec![AABB::empty(); bin_count];
+    {
+        let mut count = 0;
+        let mut aabb = AABB::empty();
+        for i in 0..bin_count {
+            count += bins[i].count;
+            aabb = aabb.join(&bins[i].aabb);
+            left_count[i] = count;
+            left_aabb[i] = aabb;
+        }
+    }
+
+    // Suffix sweep: `right[i]` covers bins `i..bin_count`.
+    let mut right_count = vec![0usize; bin_count];
+    let mut right_aabb = vec![AABB::empty(); bin_count];
+    {
+        let mut count = 0;
+        let mut aabb = AABB::empty();
+        for i in (0..bin_count).rev() {
+            count += bins[i].count;
+            aabb = aabb.join(&bins[i].aabb);
+            right_count[i] = count;
+            right_aabb[i] = aabb;
+        }
+    }
+
+    let node_area = node_aabb.surface_area();
+    let mut best_cost = f32::INFINITY;
+    let mut best_split_bin = None;
+    for i in 0..bin_count - 1 {
+        let n_l = left_count[i];
+        let n_r = right_count[i + 1];
+        if n_l == 0 || n_r == 0 {
+            continue;
+        }
+        let cost = if node_area > 0.0 {
+            config.traversal_cost
+                + (left_aabb[i].surface_area() * n_l as f32
+                    + right_aabb[i + 1].surface_area() * n_r as f32)
+                    / node_area
+                    * config.intersection_cost
+        } else {
+            (n_l + n_r) as f32
+        };
+        if cost < best_cost {
+            best_cost = cost;
+            best_split_bin = Some(i);
+        }
+    }
+
+    let split_bin = match best_split_bin {
+        Some(split_bin) => split_bin,
+        None => {
+            return if forced_split {
+                SahPlan::Split(split_median(items))
+            } else {
+                SahPlan::Leaf
+            };
+        }
+    };
+
+    if !forced_split && leaf_cost <= best_cost {
+        return SahPlan::Leaf;
+    }
+
+    let mut mid = 0;
+    for i in 0..items.len() {
+        if bin_of(&items[i].2) <= split_bin {
+            items.swap(i, mid);
+            mid += 1;
+        }
+    }
+    SahPlan::Split(mid)
+}
+
+/// Builds a subtree over `items` into a freshly allocated `Vec<BVHNode>`, for builders
+/// (like [`BVH::build_with_sah`]) where a leaf may cover more than one item, so the node
+/// count can't be precomputed from `items.len()` alone the way [`node_count_for`] does for
+/// [`build_range`]. `item_base` is the absolute offset of `items[0]` within the owning
+/// `BVH`'s eventual `shape_indices`; `depth` is this subtree's depth within the tree.
+///
+/// Every `Node`'s child indices in the returned `Vec` are relative to its own start (index
+/// 0); the caller must rebase them with [`rebase`] once the subtree is spliced into its
+/// final position, and every node's `parent_index` is left at a placeholder `0`, to be
+/// corrected by [`fixup_parent_indices`] once the whole tree is assembled. Returns the
+/// subtree's nodes together with its bounds.
+///
+/// [`BVH::build_with_sah`]: struct.BVH.html#method.build_with_sah
+/// [`node_count_for`]: fn.node_count_for.html
+/// [`build_range`]: fn.build_range.html
+/// [`rebase`]: fn.rebase.html
+/// [`fixup_parent_indices`]: fn.fixup_parent_indices.html
+///
+fn build_range_sah(
+    config: &SahConfig,
+    items: &mut [BuildItem],
+    item_base: usize,
+    depth: u32,
+) -> (Vec<BVHNode>, AABB) {
+    let plan = if items.len() <= 1 {
+        SahPlan::Leaf
+    } else {
+        sah_plan(config, items)
+    };
+
+    match plan {
+        SahPlan::Leaf => {
+            let mut aabb = AABB::empty();
+            for (_, item_aabb, _) in items.iter() {
+                aabb = aabb.join(item_aabb);
+            }
+            let node = BVHNode::Leaf {
+                parent_index: 0,
+                depth,
+                shape_index: item_base,
+                shape_count: items.len(),
+            };
+            (vec![node], aabb)
+        }
+        SahPlan::Split(mid) => {
+            let (left_items, right_items) = items.split_at_mut(mid);
+            let (mut left_nodes, left_aabb) =
+                build_range_sah(config, left_items, item_base, depth + 1);
+            let (mut right_nodes, right_aabb) =
+                build_range_sah(config, right_items, item_base + mid, depth + 1);
+
+            let left_base = 1;
+            let right_base = 1 + left_nodes.len();
+            rebase(&mut left_nodes, left_base);
+            rebase(&mut right_nodes, right_base);
+
+            let mut nodes = Vec::with_capacity(1 + left_nodes.len() + right_nodes.len());
+            nodes.push(BVHNode::Node {
+                parent_index: 0,
+                depth,
+                child_l_aabb: left_aabb,
+                child_l_index: left_base,
+                child_r_aabb: right_aabb,
+                child_r_index: right_base,
+            });
+            nodes.extend(left_nodes);
+            nodes.extend(right_nodes);
+
+            let joint_aabb = left_aabb.join(&right_aabb);
+            (nodes, joint_aabb)
+        }
+    }
+}
+
+/// Adds `offset` to every [`BVHNode::Node`]'s child indices in `nodes`, to splice a subtree
+/// built relative to its own start (index 0) into its final position within a larger node
+/// array. A `Leaf`'s `shape_index` is left untouched, since it already indexes the owning
+/// `BVH`'s `shape_indices` rather than `nodes`.
+///
+/// [`BVHNode::Node`]: enum.BVHNode.html#variant.Node
+///
+fn rebase(nodes: &mut [BVHNode], offset: usize) {
+    for node in nodes.iter_mut() {
+        if let BVHNode::Node {
+            child_l_index,
+            child_r_index,
+            ..
+        } = node
+        {
+            *child_l_index += offset;
+            *child_r_index += offset;
+        }
+    }
+}
+
+/// Fixes up every node's `parent_index` in a tree assembled bottom-up by
+/// [`build_range_sah`], whose nodes are initially written with a placeholder
+/// `parent_index` of `0`, since a subtree doesn't know its parent's absolute index until
+/// it's spliced into place.
+///
+/// [`build_range_sah`]: fn.build_range_sah.html
+///
+fn fixup_parent_indices(nodes: &mut [BVHNode]) {
+    if nodes.is_empty() {
+        return;
+    }
+    let mut stack = vec![0usize];
+    while let Some(index) = stack.pop() {
+        if let BVHNode::Node {
+            child_l_index,
+            child_r_index,
+            ..
+        } = nodes[index]
+        {
+            set_parent_index(&mut nodes[child_l_index], index);
+            set_parent_index(&mut nodes[child_r_index], index);
+            stack.push(child_l_index);
+            stack.push(child_r_index);
+        }
+    }
+}
+
+fn set_parent_index(node: &mut BVHNode, new_parent_index: usize) {
+    match node {
+        BVHNode::Leaf { parent_index, .. } | BVHNode::Node { parent_index, .. } => {
+            *parent_index = new_parent_index;
+        }
+    }
+}
+
+/// Builds the subtree over `items` into the pre-sized `nodes` slice (`nodes.len() ==
+/// node_count_for(items.len())`), where `base` is the absolute index of `nodes[0]` within
+/// the owning `BVH`, and `item_base` is the absolute offset of `items[0]` within the
+/// owning `BVH`'s eventual `shape_indices`. Returns the subtree's bounds.
+///
+/// This always recurses down to single-shape leaves; `split` chooses how each interior
+/// node's items are partitioned ([`split_median`] for [`BVH::build`] and `BVH::build_par`;
+/// [`BVH::build_with_sah`] goes through [`build_range_sah`] instead).
+///
+/// [`split_median`]: fn.split_median.html
+/// [`BVH::build`]: struct.BVH.html#method.build
+/// [`BVH::build_with_sah`]: struct.BVH.html#method.build_with_sah
+/// [`build_range_sah`]: fn.build_range_sah.html
+#[allow(clippy::too_many_arguments)]
+fn build_range(
+    nodes: &mut [BVHNode],
+    base: usize,
+    items: &mut [BuildItem],
+    item_base: usize,
+    parent_index: usize,
+    depth: u32,
+    split: &impl Fn(&mut [BuildItem]) -> usize,
+) -> AABB {
+    if items.len() == 1 {
+        let (_, aabb, _) = items[0];
+        nodes[0] = BVHNode::Leaf {
+            parent_index,
+            depth,
+            shape_index: item_base,
+            shape_count: 1,
+        };
+        return aabb;
+    }
+
+    let mid = split(items).clamp(1, items.len() - 1);
+    let (left_items, right_items) = items.split_at_mut(mid);
+    let left_node_count = node_count_for(left_items.len());
+
+    let (_, rest) = nodes.split_at_mut(1);
+    let (left_nodes, right_nodes) = rest.split_at_mut(left_node_count);
+
+    let left_base = base + 1;
+    let right_base = base + 1 + left_node_count;
+
+    let left_aabb = build_range(
+        left_nodes, left_base, left_items, item_base, base, depth + 1, split,
+    );
+    let right_aabb = build_range(
+        right_nodes,
+        right_base,
+        right_items,
+        item_base + mid,
+        base,
+        depth + 1,
+        split,
+    );
+    let joint_aabb = left_aabb.join(&right_aabb);
+
+    nodes[0] = BVHNode::Node {
+        parent_index,
+        depth,
+        child_l_aabb: left_aabb,
+        child_l_index: left_base,
+        child_r_aabb: right_aabb,
+        child_r_index: right_base,
+    };
+
+    joint_aabb
+}
+
+/// Parallel counterpart of [`build_range`]: builds the same tree (same node layout, same
+/// `split` strategy), but once a subtree's item count exceeds `threshold`, its two children
+/// are built concurrently via `rayon::join` instead of one after the other. Subtrees at or
+/// below `threshold` fall back to [`build_range`] directly, so small nodes don't pay task
+/// overhead.
+///
+/// [`build_range`]: fn.build_range.html
+///
+#[cfg(feature = "rayon")]
+#[allow(clippy::too_many_arguments)]
+fn build_range_par<S>(
+    nodes: &mut [BVHNode],
+    base: usize,
+    items: &mut [BuildItem],
+    item_base: usize,
+    parent_index: usize,
+    depth: u32,
+    split: &S,
+    threshold: usize,
+) -> AABB
+where
+    S: Fn(&mut [BuildItem]) -> usize + Sync,
+{
+    if items.len() == 1 || items.len() <= threshold {
+        return build_range(nodes, base, items, item_base, parent_index, depth, split);
+    }
+
+    let mid = split(items).clamp(1, items.len() - 1);
+    let (left_items, right_items) = items.split_at_mut(mid);
+    let left_node_count = node_count_for(left_items.len());
+
+    let (_, rest) = nodes.split_at_mut(1);
+    let (left_nodes, right_nodes) = rest.split_at_mut(left_node_count);
+
+    let left_base = base + 1;
+    let right_base = base + 1 + left_node_count;
+
+    let (left_aabb, right_aabb) = rayon::join(
+        || {
+            build_range_par(
+                left_nodes,
+                left_base,
+                left_items,
+                item_base,
+                base,
+                depth + 1,
+                split,
+                threshold,
+            )
+        },
+        || {
+            build_range_par(
+                right_nodes,
+                right_base,
+                right_items,
+                item_base + mid,
+                base,
+                depth + 1,
+                split,
+                threshold,
+            )
+        },
+    );
+    let joint_aabb = left_aabb.join(&right_aabb);
+
+    nodes[0] = BVHNode::Node {
+        parent_index,
+        depth,
+        child_l_aabb: left_aabb,
+        child_l_index: left_base,
+        child_r_aabb: right_aabb,
+        child_r_index: right_base,
+    };
+
+    joint_aabb
+}
+
+fn assign_shape_node_indices<T: BHShape>(nodes: &[BVHNode], shape_indices: &[usize], shapes: &mut [T]) {
+    for (node_index, node) in nodes.iter().enumerate() {
+        if let BVHNode::Leaf {
+            shape_index,
+            shape_count,
+            ..
+        } = *node
+        {
+            for &original_index in &shape_indices[shape_index..shape_index + shape_count] {
+                shapes[original_index].set_bh_node_index(node_index);
+            }
+        }
+    }
+}
+
+impl BVH {
+    /// Builds a `BVH` over `shapes` using the default median-split strategy.
+    ///
+    /// Shapes whose `aabb()` contains a NaN or infinite coordinate are excluded from the
+    /// tree rather than corrupting it; see [`BVH::excluded_shapes`].
+    ///
+    /// [`BVH::excluded_shapes`]: struct.BVH.html#method.excluded_shapes
+    ///
+    pub fn build<T: BHShape>(shapes: &mut [T]) -> BVH {
+        let (mut items, excluded_shapes) = collect_finite_items(shapes);
+        let mut nodes = placeholder_nodes(node_count_for(items.len()));
+        if !items.is_empty() {
+            build_range(&mut nodes, 0, &mut items, 0, 0, 0, &split_median);
+        }
+        let shape_indices: Vec<usize> = items.iter().map(|(index, _, _)| *index).collect();
+        assign_shape_node_indices(&nodes, &shape_indices, shapes);
+        BVH {
+            nodes,
+            shape_indices,
+            excluded_shapes,
+        }
+    }
+
+    /// Builds a `BVH` over `shapes` using a binned surface-area-heuristic split strategy
+    /// (see [`SahConfig`]), trading build time for a tree with lower expected traversal
+    /// cost than [`BVH::build`]'s median split.
+    ///
+    /// Shapes whose `aabb()` contains a NaN or infinite coordinate are excluded from the
+    /// tree rather than corrupting it; see [`BVH::excluded_shapes`].
+    ///
+    /// [`SahConfig`]: struct.SahConfig.html
+    /// [`BVH::build`]: struct.BVH.html#method.build
+    /// [`BVH::excluded_shapes`]: struct.BVH.html#method.excluded_shapes
+    ///
+    pub fn build_with_sah<T: BHShape>(shapes: &mut [T], config: SahConfig) -> BVH {
+        let (mut items, excluded_shapes) = collect_finite_items(shapes);
+        let nodes = if items.is_empty() {
+            Vec::new()
+        } else {
+            let (mut nodes, _) = build_range_sah(&config, &mut items, 0, 0);
+            fixup_parent_indices(&mut nodes);
+            nodes
+        };
+        let shape_indices: Vec<usize> = items.iter().map(|(index, _, _)| *index).collect();
+        assign_shape_node_indices(&nodes, &shape_indices, shapes);
+        BVH {
+            nodes,
+            shape_indices,
+            excluded_shapes,
+        }
+    }
+
+    /// Returns the indices (into the shapes slice passed to the builder) of shapes that
+    /// were excluded from the tree because their `aabb()` had a NaN or infinite extent.
+    pub fn excluded_shapes(&self) -> &[usize] {
+        &self.excluded_shapes
+    }
+
+    /// Builds a `BVH` over `shapes` like [`BVH::build`], but builds subtrees of more than
+    /// `threshold` items concurrently across a `rayon` thread pool. Node indices come out
+    /// identical to [`BVH::build`]'s, since each subtree's index range is reserved before
+    /// its two children are spawned, so `flatten()` and traversal are unaffected by
+    /// whether a tree was built serially or in parallel.
+    ///
+    /// [`BVH::build`]: struct.BVH.html#method.build
+    ///
+    #[cfg(feature = "rayon")]
+    pub fn build_par<T: BHShape + Send>(shapes: &mut [T], threshold: usize) -> BVH {
+        let (mut items, excluded_shapes) = collect_finite_items(shapes);
+        let mut nodes = placeholder_nodes(node_count_for(items.len()));
+        if !items.is_empty() {
+            build_range_par(&mut nodes, 0, &mut items, 0, 0, 0, &split_median, threshold);
+        }
+        let shape_indices: Vec<usize> = items.iter().map(|(index, _, _)| *index).collect();
+        assign_shape_node_indices(&nodes, &shape_indices, shapes);
+        BVH {
+            nodes,
+            shape_indices,
+            excluded_shapes,
+        }
+    }
+}
+
+impl BoundingHierarchy for BVH {
+    fn build<T: BHShape>(shapes: &mut [T]) -> BVH {
+        BVH::build(shapes)
+    }
+
+    fn traverse<'a, T: Bounded>(&'a self, ray: &Ray, shapes: &'a [T]) -> Vec<&'a T> {
+        let mut hit_shapes = Vec::new();
+        if self.nodes.is_empty() {
+            return hit_shapes;
+        }
+        traverse_recursive(&self.nodes, &self.shape_indices, 0, ray, shapes, &mut hit_shapes);
+        hit_shapes
+    }
+}
+
+fn traverse_recursive<'a, T: Bounded>(
+    nodes: &[BVHNode],
+    shape_indices: &[usize],
+    node_index: usize,
+    ray: &Ray,
+    shapes: &'a [T],
+    hit_shapes: &mut Vec<&'a T>,
+) {
+    match nodes[node_index] {
+        BVHNode::Node {
+            child_l_aabb,
+            child_l_index,
+            child_r_aabb,
+            child_r_index,
+            ..
+        } => {
+            if ray.intersects_aabb(&child_l_aabb) {
+                traverse_recursive(nodes, shape_indices, child_l_index, ray, shapes, hit_shapes);
+            }
+            if ray.intersects_aabb(&child_r_aabb) {
+                traverse_recursive(nodes, shape_indices, child_r_index, ray, shapes, hit_shapes);
+            }
+        }
+        BVHNode::Leaf {
+            shape_index,
+            shape_count,
+            ..
+        } => {
+            for &original_index in &shape_indices[shape_index..shape_index + shape_count] {
+                let shape = &shapes[original_index];
+                if ray.intersects_aabb(&shape.aabb()) {
+                    hit_shapes.push(shape);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BVHNode, SahConfig, BVH};
+    use crate::bounding_hierarchy::BoundingHierarchy;
+    use crate::ray::Ray;
+    use crate::testbase::{build_some_bh, build_unit_boxes, diagonal_ray, traverse_some_bh};
+
+    #[test]
+    /// Tests whether the building procedure succeeds in not failing.
+    fn test_build_bvh() {
+        build_some_bh::<BVH>();
+    }
+
+    #[test]
+    /// Runs some primitive tests for intersections of a ray with a fixed scene given as a
+    /// `BVH`.
+    fn test_traverse_bvh() {
+        traverse_some_bh::<BVH>();
+    }
+
+    #[test]
+    /// `build_with_sah` should produce a tree that still refers to every shape exactly
+    /// once (across its leaves, possibly multiple per leaf) and still finds the same hits
+    /// as the default median-split `BVH::build` on the same scene.
+    fn test_build_with_sah() {
+        let mut median_shapes = build_unit_boxes(1000);
+        let median_bvh = BVH::build(&mut median_shapes);
+
+        let mut sah_shapes = build_unit_boxes(1000);
+        let sah_bvh = BVH::build_with_sah(&mut sah_shapes, SahConfig::default());
+
+        let mut leaf_shape_indices: Vec<usize> = sah_bvh
+            .nodes
+            .iter()
+            .filter_map(|node| match *node {
+                BVHNode::Leaf {
+                    shape_index,
+                    shape_count,
+                    ..
+                } => Some(sah_bvh.shape_indices[shape_index..shape_index + shape_count].to_vec()),
+                BVHNode::Node { .. } => None,
+            })
+            .flatten()
+            .collect();
+        leaf_shape_indices.sort_unstable();
+        assert_eq!(
+            leaf_shape_indices,
+            (0..sah_shapes.len()).collect::<Vec<_>>()
+        );
+
+        let ray = diagonal_ray();
+        let expected = median_bvh.traverse(&ray, &median_shapes).len();
+        let actual = sah_bvh.traverse(&ray, &sah_shapes).len();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    /// With a `max_leaf_size` above 1, nearby shapes whose bounds make splitting them not
+    /// worth the extra traversal cost should end up merged into the same leaf, and
+    /// traversal must still find every shape a ray actually crosses.
+    fn test_build_with_sah_merges_leaves() {
+        use crate::testbase::UnitBox;
+        use crate::Point3;
+
+        // A tight cluster of boxes the cost model covers with multi-shape leaves (with the
+        // default costs, two leaves of four), plus one far-away box that should end up
+        // split off into its own leaf.
+        let mut shapes: Vec<UnitBox> = (0..8)
+            .map(|i| UnitBox::new(i, Point3::new(i as f32 * 0.1, 0.0, 0.0)))
+            .collect();
+        shapes.push(UnitBox::new(100, Point3::new(1000.0, 1000.0, 1000.0)));
+
+        let config = SahConfig {
+            max_leaf_size: 16,
+            ..SahConfig::default()
+        };
+        let bvh = BVH::build_with_sah(&mut shapes, config);
+
+        let merged_leaf_found = bvh.nodes.iter().any(|node| {
+            matches!(node, BVHNode::Leaf { shape_count, .. } if *shape_count > 1)
+        });
+        assert!(
+            merged_leaf_found,
+            "expected at least one leaf covering more than one shape"
+        );
+
+        let ray = Ray::new(Point3::new(-1.0, 0.0, 0.0), crate::Vector3::new(1.0, 0.0, 0.0));
+        let hit_ids: Vec<i32> = bvh.traverse(&ray, &shapes).iter().map(|s| s.id).collect();
+        assert_eq!(hit_ids.len(), 8);
+    }
+
+    #[test]
+    /// A shape with a NaN or infinite `aabb()` must be excluded (and reported) instead of
+    /// corrupting the tree, which should still find every finite shape.
+    fn test_build_excludes_non_finite_shapes() {
+        use crate::testbase::UnitBox;
+        use crate::Point3;
+
+        let mut shapes = build_unit_boxes(10);
+        shapes.push(UnitBox::new(100, Point3::new(f32::NAN, 0.0, 0.0)));
+        shapes.push(UnitBox::new(101, Point3::new(0.0, f32::INFINITY, 0.0)));
+
+        let bvh = BVH::build(&mut shapes);
+
+        let mut excluded = bvh.excluded_shapes().to_vec();
+        excluded.sort_unstable();
+        assert_eq!(excluded, vec![10, 11]);
+
+        let ray = diagonal_ray();
+        let hit_ids: Vec<i32> = bvh.traverse(&ray, &shapes).iter().map(|s| s.id).collect();
+        assert_eq!(hit_ids.len(), 10);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    /// `build_par`, run with a threshold low enough to force it to actually recurse in
+    /// parallel, should produce a tree with the same node layout and the same hits as the
+    /// serial `BVH::build` on the same scene.
+    fn test_build_par() {
+        let mut serial_shapes = build_unit_boxes(1000);
+        let serial_bvh = BVH::build(&mut serial_shapes);
+
+        let mut par_shapes = build_unit_boxes(1000);
+        let par_bvh = BVH::build_par(&mut par_shapes, 8);
+
+        assert_eq!(par_bvh.nodes.len(), serial_bvh.nodes.len());
+
+        let ray = diagonal_ray();
+        let expected = serial_bvh.traverse(&ray, &serial_shapes).len();
+        let actual = par_bvh.traverse(&ray, &par_shapes).len();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    /// `threshold = 0` ("always parallelize") must not panic: every subtree, including a
+    /// 1-item one, has to bottom out in a leaf rather than reaching the `split` call with
+    /// nothing left to partition.
+    fn test_build_par_zero_threshold() {
+        let mut shapes = build_unit_boxes(8);
+        let bvh = BVH::build_par(&mut shapes, 0);
+
+        let ray = diagonal_ray();
+        assert_eq!(bvh.traverse(&ray, &shapes).len(), 8);
+    }
+}
@@ -1,33 +1,52 @@
 //! This module exports methods to flatten the `BVH` and traverse it iteratively.
+//!
+//! With the `bytemuck` feature enabled, the flattened representation ([`FlatNode`]) is
+//! laid out so it can be uploaded to a GPU buffer without a manual copy, see
+//! [`AsBytes::as_bytes`].
+//!
+//! [`FlatNode`]: struct.FlatNode.html
+//! [`AsBytes::as_bytes`]: trait.AsBytes.html#tymethod.as_bytes
 
 use crate::aabb::{Bounded, AABB};
 use crate::bounding_hierarchy::{BHShape, BoundingHierarchy};
 use crate::bvh::{BVHNode, BVH};
 use crate::ray::Ray;
+use crate::{Point3, Vector3};
 
 /// A structure of a node of a flat [`BVH`]. The structure of the nodes allows for an
 /// iterative traversal approach without the necessity to maintain a stack or queue.
 ///
+/// With the `bytemuck` feature enabled, `FlatNode` is `#[repr(C)]` and derives
+/// `Pod`/`Zeroable`, so a [`FlatBVH`] can be reinterpreted as a raw byte slice via
+/// [`AsBytes::as_bytes`] and uploaded directly into a GPU buffer. The field declaration
+/// order is the layout order: `aabb` (24 bytes: min.x, min.y, min.z, max.x, max.y, max.z),
+/// then `entry_index`, `exit_index`, `shape_index` (4 bytes each), for 36 bytes total.
+///
 /// [`BVH`]: ../bvh/struct.BVH.html
+/// [`FlatBVH`]: type.FlatBVH.html
+/// [`AsBytes::as_bytes`]: trait.AsBytes.html#tymethod.as_bytes
 ///
+#[cfg_attr(feature = "bytemuck", repr(C))]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+#[derive(Debug, Clone, Copy)]
 pub struct FlatNode {
     /// The [`AABB`] of the [`BVH`] node. Prior to testing the [`AABB`] bounds,
-    /// the `entry_index` must be checked. In case the entry_index is [`u32::max_value()`],
+    /// the `entry_index` must be checked. In case the entry_index is [`u32::MAX`],
     /// the [`AABB`] is undefined.
     ///
     /// [`AABB`]: ../aabb/struct.AABB.html
     /// [`BVH`]: ../bvh/struct.BVH.html
-    /// [`u32::max_value()`]: https://doc.rust-lang.org/std/u32/constant.MAX.html
+    /// [`u32::MAX`]: https://doc.rust-lang.org/std/primitive.u32.html#associatedconstant.MAX
     ///
     pub aabb: AABB,
 
     /// The index of the `FlatNode` to jump to, if the [`AABB`] test is positive.
-    /// If this value is [`u32::max_value()`] then the current node is a leaf node.
+    /// If this value is [`u32::MAX`] then the current node is a leaf node.
     /// Leaf nodes contain a shape index and an exit index. In leaf nodes the
     /// [`AABB`] is undefined.
     ///
     /// [`AABB`]: ../aabb/struct.AABB.html
-    /// [`u32::max_value()`]: https://doc.rust-lang.org/std/u32/constant.MAX.html
+    /// [`u32::MAX`]: https://doc.rust-lang.org/std/primitive.u32.html#associatedconstant.MAX
     ///
     pub entry_index: u32,
 
@@ -42,7 +61,14 @@ pub struct FlatNode {
 }
 
 impl BVHNode {
-    /// Counts number of nodes in the subtree.
+    /// Counts the number of flattened nodes the subtree expands to: one per `Node`, and one
+    /// per shape held by a `Leaf` (since a multi-shape leaf, produced by
+    /// [`BVH::build_with_sah`] when its cost model prefers stopping over splitting
+    /// further, flattens to a chain of that many single-shape [`FlatNode`] entries).
+    ///
+    /// [`BVH::build_with_sah`]: ../bvh/struct.BVH.html#method.build_with_sah
+    /// [`FlatNode`]: struct.FlatNode.html
+    ///
     fn count_nodes(&self, nodes: &[BVHNode]) -> usize {
         match *self {
             BVHNode::Node {
@@ -53,19 +79,26 @@ impl BVHNode {
                 1 + nodes[child_l_index].count_nodes(nodes)
                     + nodes[child_r_index].count_nodes(nodes)
             }
-            BVHNode::Leaf { .. } => 1,
+            BVHNode::Leaf { shape_count, .. } => shape_count,
         }
     }
 
     /// Flattens the [`BVH`], so that it can be traversed in an iterative manner.
     /// This method constructs custom flat nodes using the `constructor`.
     ///
+    /// A `Leaf` covering more than one shape (see [`BVH::build_with_sah`]) is flattened
+    /// into that many consecutive single-shape entries, chained together via their exit
+    /// indices: every entry but the last exits into the next entry in the group, and only
+    /// the last uses the leaf's real external exit index.
+    ///
     /// [`BVH`]: ../bvh/struct.BVH.html
+    /// [`BVH::build_with_sah`]: ../bvh/struct.BVH.html#method.build_with_sah
     ///
     pub fn flatten_custom<F>(
         &self,
         this_aabb: &AABB,
         nodes: &[BVHNode],
+        shape_indices: &[usize],
         flattened_node_index: usize,
         exit_index: usize,
         constructor: &mut F,
@@ -83,15 +116,11 @@ impl BVHNode {
                 let left_subtree_num_nodes = nodes[child_l_index].count_nodes(nodes);
                 let l_index = 1 + flattened_node_index;
                 let r_index = 1 + flattened_node_index + left_subtree_num_nodes;
-                constructor(
-                    this_aabb,
-                    l_index as u32,
-                    exit_index as u32,
-                    u32::max_value(),
-                );
+                constructor(this_aabb, l_index as u32, exit_index as u32, u32::MAX);
                 nodes[child_l_index].flatten_custom(
                     child_l_aabb,
                     nodes,
+                    shape_indices,
                     l_index,
                     r_index,
                     constructor,
@@ -99,18 +128,35 @@ impl BVHNode {
                 nodes[child_r_index].flatten_custom(
                     child_r_aabb,
                     nodes,
+                    shape_indices,
                     r_index,
                     exit_index,
                     constructor,
                 );
             }
-            BVHNode::Leaf { shape_index, .. } => {
-                constructor(
-                    &AABB::empty(),
-                    u32::max_value(),
-                    exit_index as u32,
-                    shape_index as u32,
-                );
+            BVHNode::Leaf {
+                shape_index,
+                shape_count,
+                ..
+            } => {
+                for (i, &original_index) in
+                    shape_indices[shape_index..shape_index + shape_count]
+                        .iter()
+                        .enumerate()
+                {
+                    let is_last = i + 1 == shape_count;
+                    let this_exit_index = if is_last {
+                        exit_index
+                    } else {
+                        flattened_node_index + i + 1
+                    };
+                    constructor(
+                        &AABB::empty(),
+                        u32::MAX,
+                        this_exit_index as u32,
+                        original_index as u32,
+                    );
+                }
             }
         }
     }
@@ -121,11 +167,35 @@ impl BVHNode {
 ///
 /// [`BVH`]: ../bvh/struct.BVH.html
 /// [`FlatNode`]: struct.FlatNode.html
-/// [`FlatBVH`]: struct.FlatBVH.html
+/// [`FlatBVH`]: type.FlatBVH.html
 ///
 #[allow(clippy::upper_case_acronyms)]
 pub type FlatBVH = Vec<FlatNode>;
 
+/// Exposes a [`FlatBVH`] as a raw byte slice, so it can be uploaded straight into a GPU
+/// buffer. Only available with the `bytemuck` feature, which makes [`FlatNode`]
+/// `#[repr(C)]` and derives `Pod`/`Zeroable` for it.
+///
+/// [`FlatBVH`]: type.FlatBVH.html
+/// [`FlatNode`]: struct.FlatNode.html
+///
+#[cfg(feature = "bytemuck")]
+pub trait AsBytes {
+    /// Reinterprets the flattened nodes as a byte slice, using the layout documented on
+    /// [`FlatNode`].
+    ///
+    /// [`FlatNode`]: struct.FlatNode.html
+    ///
+    fn as_bytes(&self) -> &[u8];
+}
+
+#[cfg(feature = "bytemuck")]
+impl AsBytes for FlatBVH {
+    fn as_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(self.as_slice())
+    }
+}
+
 impl BVH {
     /// Flattens the [`BVH`] so that it can be traversed iteratively.
     /// Constructs the flat nodes using the supplied function.
@@ -198,23 +268,26 @@ impl BVH {
     ///     shape_index: u32,
     /// }
     ///
-    /// let custom_constructor = |aabb: &AABB, entry, exit, shape_index| {
-    ///     CustomStruct {
+    /// let mut shapes = create_bhshapes();
+    /// let bvh = BVH::build(&mut shapes);
+    /// let mut custom_flat_bvh: Vec<CustomStruct> = Vec::new();
+    /// let mut custom_constructor = |aabb: &AABB, entry, exit, shape_index| {
+    ///     custom_flat_bvh.push(CustomStruct {
     ///         aabb: *aabb,
     ///         entry_index: entry,
     ///         exit_index: exit,
     ///         shape_index: shape_index,
-    ///     }
+    ///     });
     /// };
-    ///
-    /// let mut shapes = create_bhshapes();
-    /// let bvh = BVH::build(&mut shapes);
-    /// let custom_flat_bvh = bvh.flatten_custom(&custom_constructor);
+    /// bvh.flatten_custom(&mut custom_constructor);
     /// ```
     pub fn flatten_custom<F>(&self, constructor: &mut F)
     where
         F: FnMut(&AABB, u32, u32, u32),
     {
+        if self.nodes.is_empty() {
+            return;
+        }
         let root_aabb = match self.nodes[0] {
             BVHNode::Node {
                 ref child_l_aabb,
@@ -223,7 +296,15 @@ impl BVH {
             } => child_l_aabb.join(child_r_aabb),
             BVHNode::Leaf { .. } => AABB::empty(),
         };
-        self.nodes[0].flatten_custom(&root_aabb, &self.nodes, 0, self.nodes.len(), constructor);
+        let total_flat_nodes = self.nodes[0].count_nodes(&self.nodes);
+        self.nodes[0].flatten_custom(
+            &root_aabb,
+            &self.nodes,
+            &self.shape_indices,
+            0,
+            total_flat_nodes,
+            constructor,
+        );
     }
 
     /// Flattens the [`BVH`] so that it can be traversed iteratively.
@@ -302,7 +383,7 @@ impl BVH {
 impl BoundingHierarchy for FlatBVH {
     /// A [`FlatBVH`] is built from a regular [`BVH`] using the [`flatten`] method.
     ///
-    /// [`FlatBVH`]: struct.FlatBVH.html
+    /// [`FlatBVH`]: type.FlatBVH.html
     /// [`BVH`]: ../bvh/struct.BVH.html
     ///
     fn build<T: BHShape>(shapes: &mut [T]) -> FlatBVH {
@@ -312,7 +393,7 @@ impl BoundingHierarchy for FlatBVH {
 
     /// Traverses a [`FlatBVH`] structure iteratively.
     ///
-    /// [`FlatBVH`]: struct.FlatBVH.html
+    /// [`FlatBVH`]: type.FlatBVH.html
     ///
     /// # Examples
     ///
@@ -373,7 +454,7 @@ impl BoundingHierarchy for FlatBVH {
     /// let flat_bvh = FlatBVH::build(&mut shapes);
     /// let hit_shapes = flat_bvh.traverse(&ray, &shapes);
     /// ```
-    fn traverse<'a, T: Bounded>(&'a self, ray: &Ray, shapes: &'a [T]) -> Vec<&T> {
+    fn traverse<'a, T: Bounded>(&'a self, ray: &Ray, shapes: &'a [T]) -> Vec<&'a T> {
         let mut hit_shapes = Vec::new();
         let mut index = 0;
 
@@ -384,7 +465,7 @@ impl BoundingHierarchy for FlatBVH {
         while index < max_length {
             let node = &self[index];
 
-            if node.entry_index == u32::max_value() {
+            if node.entry_index == u32::MAX {
                 // If the entry_index is MAX_UINT32, then it's a leaf node.
                 let shape = &shapes[node.shape_index as usize];
                 if ray.intersects_aabb(&shape.aabb()) {
@@ -409,7 +490,7 @@ impl BoundingHierarchy for FlatBVH {
 
     /// Prints a textual representation of a [`FlatBVH`].
     ///
-    /// [`FlatBVH`]: struct.FlatBVH.html
+    /// [`FlatBVH`]: type.FlatBVH.html
     ///
     fn pretty_print(&self) {
         for (i, node) in self.iter().enumerate() {
@@ -421,6 +502,217 @@ impl BoundingHierarchy for FlatBVH {
     }
 }
 
+/// Closest-hit traversal of a [`FlatBVH`]: unlike [`BoundingHierarchy::traverse`], which
+/// collects every shape the ray's `AABB` crosses, this keeps a running best distance and
+/// prunes whole subtrees once they cannot beat it.
+///
+/// [`FlatBVH`]: type.FlatBVH.html
+/// [`BoundingHierarchy::traverse`]: ../bounding_hierarchy/trait.BoundingHierarchy.html#tymethod.traverse
+///
+pub trait NearestTraversal<T: Bounded> {
+    /// Walks the flat tree in its fixed node order, following `entry_index` only while the
+    /// node's AABB entry distance is still less than the current best hit's `t`; otherwise
+    /// the `exit_index` branch is taken immediately, skipping the subtree. At each leaf,
+    /// `intersect` is called to test the actual shape and, on a hit, potentially lower the
+    /// running best distance. Returns the closest hit shape and its distance, if any.
+    fn traverse_nearest<'a, F>(
+        &'a self,
+        ray: &Ray,
+        shapes: &'a [T],
+        intersect: F,
+    ) -> Option<(&'a T, f32)>
+    where
+        F: Fn(&'a T, &Ray) -> Option<f32>;
+}
+
+impl<T: Bounded> NearestTraversal<T> for FlatBVH {
+    fn traverse_nearest<'a, F>(
+        &'a self,
+        ray: &Ray,
+        shapes: &'a [T],
+        intersect: F,
+    ) -> Option<(&'a T, f32)>
+    where
+        F: Fn(&'a T, &Ray) -> Option<f32>,
+    {
+        let mut index = 0;
+        let max_length = self.len();
+        let mut best: Option<(&'a T, f32)> = None;
+
+        while index < max_length {
+            let node = &self[index];
+
+            if node.entry_index == u32::MAX {
+                // Leaf: test the actual shape and keep it if it beats the current best.
+                let shape = &shapes[node.shape_index as usize];
+                if let Some(t) = intersect(shape, ray) {
+                    if best.is_none_or(|(_, best_t)| t < best_t) {
+                        best = Some((shape, t));
+                    }
+                }
+                index = node.exit_index as usize;
+            } else {
+                let t_max = best.map_or(f32::INFINITY, |(_, t)| t);
+                match ray.intersects_aabb_dist(&node.aabb) {
+                    Some(entry_dist) if entry_dist < t_max => index = node.entry_index as usize,
+                    _ => index = node.exit_index as usize,
+                }
+            }
+        }
+
+        best
+    }
+}
+
+/// A plane, used by [`SpatialQuery::traverse_frustum`] to describe a view frustum as six
+/// half-spaces. A point `p` is on the plane's positive (inside) side when
+/// `normal.dot(p) + d >= 0`; frustum planes are expected to have inward-facing normals,
+/// i.e. the frustum's interior is the intersection of all six positive half-spaces.
+///
+/// [`SpatialQuery::traverse_frustum`]: trait.SpatialQuery.html#tymethod.traverse_frustum
+///
+#[derive(Debug, Clone, Copy)]
+pub struct Plane {
+    /// The plane's (not necessarily normalized) normal vector, facing towards the
+    /// frustum's interior.
+    pub normal: Vector3,
+    /// The plane's distance term, such that `normal.dot(p) + d == 0` for points `p` on
+    /// the plane.
+    pub d: f32,
+}
+
+impl Plane {
+    /// Creates a new `Plane` from a normal vector and distance term.
+    pub fn new(normal: Vector3, d: f32) -> Plane {
+        Plane { normal, d }
+    }
+
+    fn signed_distance(&self, p: &Point3) -> f32 {
+        self.normal.x * p.x + self.normal.y * p.y + self.normal.z * p.z + self.d
+    }
+}
+
+fn aabb_overlaps(a: &AABB, b: &AABB) -> bool {
+    a.min.x <= b.max.x
+        && a.max.x >= b.min.x
+        && a.min.y <= b.max.y
+        && a.max.y >= b.min.y
+        && a.min.z <= b.max.z
+        && a.max.z >= b.min.z
+}
+
+fn aabb_contains_point(aabb: &AABB, p: &Point3) -> bool {
+    p.x >= aabb.min.x
+        && p.x <= aabb.max.x
+        && p.y >= aabb.min.y
+        && p.y <= aabb.max.y
+        && p.z >= aabb.min.z
+        && p.z <= aabb.max.z
+}
+
+/// An `AABB` is outside the frustum if, for any plane, even its most-positive corner (the
+/// "positive vertex": the corner furthest along that plane's normal) is on the negative
+/// side. This is the standard AABB/frustum test used for view-frustum culling.
+fn aabb_in_frustum(aabb: &AABB, planes: &[Plane; 6]) -> bool {
+    planes.iter().all(|plane| {
+        let positive_vertex = Point3::new(
+            if plane.normal.x >= 0.0 {
+                aabb.max.x
+            } else {
+                aabb.min.x
+            },
+            if plane.normal.y >= 0.0 {
+                aabb.max.y
+            } else {
+                aabb.min.y
+            },
+            if plane.normal.z >= 0.0 {
+                aabb.max.z
+            } else {
+                aabb.min.z
+            },
+        );
+        plane.signed_distance(&positive_vertex) >= 0.0
+    })
+}
+
+/// Walks a [`FlatBVH`] in its fixed node order, following `entry_index` only while `query`
+/// accepts the node's `AABB` and `exit_index` otherwise, so whole subtrees that fail the
+/// predicate are skipped. This is the same entry/exit-index machinery [`traverse`] and
+/// [`traverse_nearest`] use for ray queries, generalized to any "does this AABB pass?"
+/// predicate, which is enough to build AABB overlap, point containment, and frustum
+/// queries on top of it (see [`SpatialQuery`]).
+///
+/// [`FlatBVH`]: type.FlatBVH.html
+/// [`traverse`]: ../bounding_hierarchy/trait.BoundingHierarchy.html#tymethod.traverse
+/// [`traverse_nearest`]: trait.NearestTraversal.html#tymethod.traverse_nearest
+/// [`SpatialQuery`]: trait.SpatialQuery.html
+///
+fn traverse_flat<'a, T: Bounded, Q: Fn(&AABB) -> bool>(
+    flat_bvh: &'a FlatBVH,
+    shapes: &'a [T],
+    query: Q,
+) -> Vec<&'a T> {
+    let mut hit_shapes = Vec::new();
+    let mut index = 0;
+    let max_length = flat_bvh.len();
+
+    while index < max_length {
+        let node = &flat_bvh[index];
+
+        if node.entry_index == u32::MAX {
+            let shape = &shapes[node.shape_index as usize];
+            if query(&shape.aabb()) {
+                hit_shapes.push(shape);
+            }
+            index = node.exit_index as usize;
+        } else if query(&node.aabb) {
+            index = node.entry_index as usize;
+        } else {
+            index = node.exit_index as usize;
+        }
+    }
+
+    hit_shapes
+}
+
+/// Spatial queries against a [`FlatBVH`] beyond ray intersection: AABB overlap (collision
+/// broad-phase), point containment, and view-frustum culling. Each returns the shapes
+/// whose leaf AABBs satisfy the query, built on top of the same generic [`traverse_flat`]
+/// walk.
+///
+/// [`FlatBVH`]: type.FlatBVH.html
+/// [`traverse_flat`]: fn.traverse_flat.html
+///
+pub trait SpatialQuery<T: Bounded> {
+    /// Returns every shape whose `AABB` overlaps `query`.
+    fn traverse_aabb<'a>(&'a self, query: &AABB, shapes: &'a [T]) -> Vec<&'a T>;
+
+    /// Returns every shape whose `AABB` contains `point`.
+    fn traverse_point<'a>(&'a self, point: Point3, shapes: &'a [T]) -> Vec<&'a T>;
+
+    /// Returns every shape whose `AABB` is at least partially inside the frustum defined
+    /// by `planes` (inward-facing normals, see [`Plane`]).
+    ///
+    /// [`Plane`]: struct.Plane.html
+    ///
+    fn traverse_frustum<'a>(&'a self, planes: &[Plane; 6], shapes: &'a [T]) -> Vec<&'a T>;
+}
+
+impl<T: Bounded> SpatialQuery<T> for FlatBVH {
+    fn traverse_aabb<'a>(&'a self, query: &AABB, shapes: &'a [T]) -> Vec<&'a T> {
+        traverse_flat(self, shapes, |aabb| aabb_overlaps(aabb, query))
+    }
+
+    fn traverse_point<'a>(&'a self, point: Point3, shapes: &'a [T]) -> Vec<&'a T> {
+        traverse_flat(self, shapes, |aabb| aabb_contains_point(aabb, &point))
+    }
+
+    fn traverse_frustum<'a>(&'a self, planes: &[Plane; 6], shapes: &'a [T]) -> Vec<&'a T> {
+        traverse_flat(self, shapes, |aabb| aabb_in_frustum(aabb, planes))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::flat_bvh::FlatBVH;
@@ -438,6 +730,174 @@ mod tests {
     fn test_traverse_flat_bvh() {
         traverse_some_bh::<FlatBVH>();
     }
+
+    #[test]
+    /// A `BVH` with zero nodes (every shape excluded for having a non-finite `aabb()`, or
+    /// no shapes at all) must flatten to an empty `FlatBVH` rather than panicking on an
+    /// out-of-bounds `nodes[0]`.
+    fn test_flatten_empty_bvh() {
+        use crate::bounding_hierarchy::BoundingHierarchy;
+        use crate::bvh::BVH;
+        use crate::testbase::UnitBox;
+        use crate::Point3;
+
+        let mut shapes = vec![
+            UnitBox::new(0, Point3::new(f32::NAN, 0.0, 0.0)),
+            UnitBox::new(1, Point3::new(0.0, f32::INFINITY, 0.0)),
+            UnitBox::new(2, Point3::new(f32::NEG_INFINITY, 0.0, 0.0)),
+        ];
+        let bvh = BVH::build(&mut shapes);
+        assert!(bvh.nodes.is_empty());
+
+        let flat_bvh = bvh.flatten();
+        assert!(flat_bvh.is_empty());
+
+        let flat_bvh = FlatBVH::build(&mut Vec::<UnitBox>::new());
+        assert!(flat_bvh.is_empty());
+    }
+
+    #[test]
+    /// The closest-hit traversal should find the same nearest box that a linear scan over
+    /// all boxes' slab entry distances would.
+    fn test_traverse_nearest_flat_bvh() {
+        use crate::aabb::Bounded;
+        use crate::bounding_hierarchy::BoundingHierarchy;
+        use crate::flat_bvh::NearestTraversal;
+        use crate::testbase::{build_unit_boxes, diagonal_ray};
+
+        let mut shapes = build_unit_boxes(1000);
+        let ray = diagonal_ray();
+        let flat_bvh = FlatBVH::build(&mut shapes);
+
+        let (nearest, t) = flat_bvh
+            .traverse_nearest(&ray, &shapes, |shape, ray| ray.intersects_aabb_dist(&shape.aabb()))
+            .expect("ray should hit at least one box");
+
+        let expected_t = shapes
+            .iter()
+            .filter_map(|shape| ray.intersects_aabb_dist(&shape.aabb()))
+            .fold(f32::INFINITY, f32::min);
+
+        assert_eq!(t, expected_t);
+        assert_eq!(ray.intersects_aabb_dist(&nearest.aabb()), Some(t));
+    }
+
+    #[test]
+    /// `traverse_aabb` should return exactly the boxes whose AABBs overlap the query box,
+    /// matching a linear scan.
+    fn test_traverse_aabb_flat_bvh() {
+        use crate::aabb::{Bounded, AABB};
+        use crate::bounding_hierarchy::BoundingHierarchy;
+        use crate::flat_bvh::SpatialQuery;
+        use crate::testbase::build_unit_boxes;
+        use crate::Point3;
+
+        let mut shapes = build_unit_boxes(20);
+        let flat_bvh = FlatBVH::build(&mut shapes);
+
+        let query = AABB::with_bounds(Point3::new(2.6, 2.6, 2.6), Point3::new(5.4, 5.4, 5.4));
+        let mut hit_ids: Vec<i32> = flat_bvh
+            .traverse_aabb(&query, &shapes)
+            .iter()
+            .map(|shape| shape.id)
+            .collect();
+        hit_ids.sort_unstable();
+
+        let mut expected_ids: Vec<i32> = shapes
+            .iter()
+            .filter(|shape| {
+                let aabb = shape.aabb();
+                query.min.x <= aabb.max.x
+                    && query.max.x >= aabb.min.x
+                    && query.min.y <= aabb.max.y
+                    && query.max.y >= aabb.min.y
+                    && query.min.z <= aabb.max.z
+                    && query.max.z >= aabb.min.z
+            })
+            .map(|shape| shape.id)
+            .collect();
+        expected_ids.sort_unstable();
+
+        assert!(!expected_ids.is_empty());
+        assert_eq!(hit_ids, expected_ids);
+    }
+
+    #[test]
+    /// `traverse_point` should return exactly the box containing the query point.
+    fn test_traverse_point_flat_bvh() {
+        use crate::bounding_hierarchy::BoundingHierarchy;
+        use crate::flat_bvh::SpatialQuery;
+        use crate::testbase::build_unit_boxes;
+        use crate::Point3;
+
+        let mut shapes = build_unit_boxes(20);
+        let flat_bvh = FlatBVH::build(&mut shapes);
+
+        let hits = flat_bvh.traverse_point(Point3::new(7.0, 7.0, 7.0), &shapes);
+        let hit_ids: Vec<i32> = hits.iter().map(|shape| shape.id).collect();
+        assert_eq!(hit_ids, vec![7]);
+    }
+
+    #[test]
+    /// `traverse_frustum` with planes bounding a sub-cube of the diagonal scene should
+    /// return exactly the boxes that lie at least partly inside it.
+    fn test_traverse_frustum_flat_bvh() {
+        use crate::bounding_hierarchy::BoundingHierarchy;
+        use crate::flat_bvh::{Plane, SpatialQuery};
+        use crate::testbase::build_unit_boxes;
+        use crate::Vector3;
+
+        let mut shapes = build_unit_boxes(20);
+        let flat_bvh = FlatBVH::build(&mut shapes);
+
+        // Half-spaces bounding [2.6, 5.4] on every axis, with inward-facing normals.
+        let planes = [
+            Plane::new(Vector3::new(1.0, 0.0, 0.0), -2.6),
+            Plane::new(Vector3::new(-1.0, 0.0, 0.0), 5.4),
+            Plane::new(Vector3::new(0.0, 1.0, 0.0), -2.6),
+            Plane::new(Vector3::new(0.0, -1.0, 0.0), 5.4),
+            Plane::new(Vector3::new(0.0, 0.0, 1.0), -2.6),
+            Plane::new(Vector3::new(0.0, 0.0, -1.0), 5.4),
+        ];
+
+        let mut hit_ids: Vec<i32> = flat_bvh
+            .traverse_frustum(&planes, &shapes)
+            .iter()
+            .map(|shape| shape.id)
+            .collect();
+        hit_ids.sort_unstable();
+
+        assert_eq!(hit_ids, vec![3, 4, 5]);
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    /// Verifies the documented `FlatNode` layout: 36 bytes per node, with fields in
+    /// `aabb`, `entry_index`, `exit_index`, `shape_index` order, matching a GLSL/WGSL
+    /// struct uploaded via `AsBytes::as_bytes`.
+    fn test_flat_node_bytemuck_layout() {
+        use crate::aabb::AABB;
+        use crate::flat_bvh::{AsBytes, FlatNode};
+        use crate::Point3;
+        use std::mem::size_of;
+
+        assert_eq!(size_of::<FlatNode>(), 36);
+
+        let node = FlatNode {
+            aabb: AABB::with_bounds(Point3::new(1.0, 2.0, 3.0), Point3::new(4.0, 5.0, 6.0)),
+            entry_index: 7,
+            exit_index: 8,
+            shape_index: 9,
+        };
+        let flat_bvh: FlatBVH = vec![node];
+        let bytes = flat_bvh.as_bytes();
+
+        assert_eq!(bytes.len(), 36);
+        assert_eq!(&bytes[0..4], &1.0f32.to_ne_bytes());
+        assert_eq!(&bytes[24..28], &7u32.to_ne_bytes());
+        assert_eq!(&bytes[28..32], &8u32.to_ne_bytes());
+        assert_eq!(&bytes[32..36], &9u32.to_ne_bytes());
+    }
 }
 
 #[cfg(all(feature = "bench", test))]
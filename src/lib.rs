@@ -0,0 +1,22 @@
+//! A crate which exports rays, axis-aligned bounding boxes, and binary bounding
+//! volume hierarchies.
+//!
+//! ## About
+//!
+//! This crate can be used for applications which contain intersection computations of rays
+//! with primitives. For this purpose a binary tree BVH (Bounding Volume Hierarchy) is of great
+//! use if the scene which the ray traverses contains a huge number of primitives. With a BVH the
+//! intersection test complexity is reduced from O(n) to O(log2(n)) at the cost of building
+//! the BVH once in advance.
+
+mod vec3;
+
+pub mod aabb;
+pub mod axis;
+pub mod bounding_hierarchy;
+pub mod bvh;
+pub mod flat_bvh;
+pub mod ray;
+pub mod testbase;
+
+pub use crate::vec3::{Point3, Vector3};
@@ -0,0 +1,57 @@
+//! This module defines a Ray structure and intersection algorithms for axis-aligned
+//! bounding boxes.
+
+use crate::aabb::AABB;
+use crate::{Point3, Vector3};
+
+/// A struct which defines a ray and some of its cached properties.
+#[derive(Debug, Clone, Copy)]
+pub struct Ray {
+    /// The ray's starting point.
+    pub origin: Point3,
+
+    /// The ray's direction.
+    pub direction: Vector3,
+
+    /// The precomputed reciprocal of `direction`, used to speed up the slab test in
+    /// [`intersects_aabb`] by turning divisions into multiplications.
+    ///
+    /// [`intersects_aabb`]: struct.Ray.html#method.intersects_aabb
+    ///
+    pub inv_direction: Vector3,
+}
+
+impl Ray {
+    /// Creates a new `Ray` from an `origin` and a `direction`.
+    pub fn new(origin: Point3, direction: Vector3) -> Ray {
+        Ray {
+            origin,
+            direction,
+            inv_direction: direction.reciprocal(),
+        }
+    }
+
+    /// Returns `true` if this ray intersects `aabb`.
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        self.intersects_aabb_dist(aabb).is_some()
+    }
+
+    /// Returns the distance along the ray at which it enters `aabb` (clamped to `0.0`, so
+    /// a ray starting inside the box enters at `0.0`), or `None` if the ray misses it.
+    pub fn intersects_aabb_dist(&self, aabb: &AABB) -> Option<f32> {
+        let t_min = (aabb.min - self.origin).component_mul(&self.inv_direction);
+        let t_max = (aabb.max - self.origin).component_mul(&self.inv_direction);
+
+        let t1 = Vector3::new(t_min.x.min(t_max.x), t_min.y.min(t_max.y), t_min.z.min(t_max.z));
+        let t2 = Vector3::new(t_min.x.max(t_max.x), t_min.y.max(t_max.y), t_min.z.max(t_max.z));
+
+        let t_near = t1.x.max(t1.y).max(t1.z).max(0.0);
+        let t_far = t2.x.min(t2.y).min(t2.z);
+
+        if t_far >= t_near {
+            Some(t_near)
+        } else {
+            None
+        }
+    }
+}
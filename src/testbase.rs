@@ -0,0 +1,79 @@
+//! Helpers shared by this crate's tests: a trivial `Bounded`/`BHShape` fixture and a
+//! couple of generic build/traverse smoke tests usable against any `BoundingHierarchy`
+//! implementation (`BVH`, `FlatBVH`, ...).
+
+use crate::aabb::{Bounded, AABB};
+use crate::bounding_hierarchy::{BHShape, BoundingHierarchy};
+use crate::ray::Ray;
+use crate::{Point3, Vector3};
+
+/// A unit cube centered at `pos`, used as a minimal fixture shape in tests.
+pub struct UnitBox {
+    /// An arbitrary identifier, useful for telling hit shapes apart in assertions.
+    pub id: i32,
+    /// The center of the box.
+    pub pos: Point3,
+    node_index: usize,
+}
+
+impl UnitBox {
+    /// Creates a new `UnitBox` centered at `pos`.
+    pub fn new(id: i32, pos: Point3) -> UnitBox {
+        UnitBox {
+            id,
+            pos,
+            node_index: 0,
+        }
+    }
+}
+
+impl Bounded for UnitBox {
+    fn aabb(&self) -> AABB {
+        let min = self.pos + Vector3::new(-0.5, -0.5, -0.5);
+        let max = self.pos + Vector3::new(0.5, 0.5, 0.5);
+        AABB::with_bounds(min, max)
+    }
+}
+
+impl BHShape for UnitBox {
+    fn set_bh_node_index(&mut self, index: usize) {
+        self.node_index = index;
+    }
+
+    fn bh_node_index(&self) -> usize {
+        self.node_index
+    }
+}
+
+/// Creates `n` `UnitBox`es spaced one unit apart along the diagonal, starting at the
+/// origin.
+pub fn build_unit_boxes(n: i32) -> Vec<UnitBox> {
+    (0..n)
+        .map(|i| UnitBox::new(i, Point3::new(i as f32, i as f32, i as f32)))
+        .collect()
+}
+
+/// A ray from `(-1, -1, -1)` along the positive x/y/z diagonal, which crosses every box
+/// produced by [`build_unit_boxes`].
+///
+/// [`build_unit_boxes`]: fn.build_unit_boxes.html
+///
+pub fn diagonal_ray() -> Ray {
+    Ray::new(Point3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 1.0, 1.0))
+}
+
+/// Smoke-tests that building `BH` over 1000 unit boxes doesn't panic.
+pub fn build_some_bh<BH: BoundingHierarchy>() -> (Vec<UnitBox>, BH) {
+    let mut shapes = build_unit_boxes(1000);
+    let bh = BH::build(&mut shapes);
+    (shapes, bh)
+}
+
+/// Smoke-tests that traversing `BH` over 1000 unit boxes returns a non-empty,
+/// non-panicking result.
+pub fn traverse_some_bh<BH: BoundingHierarchy>() {
+    let (shapes, bh) = build_some_bh::<BH>();
+    let ray = diagonal_ray();
+    let hit_shapes = bh.traverse(&ray, &shapes);
+    assert!(!hit_shapes.is_empty());
+}
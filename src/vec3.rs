@@ -0,0 +1,123 @@
+//! Minimal `f32` point/vector types used throughout the crate, so that `aabb`, `ray` and
+//! `bvh` don't need to agree on an external linear algebra crate.
+
+use std::ops::{Add, Index, Mul, Sub};
+
+/// A point in 3D space.
+///
+/// With the `bytemuck` feature enabled, this is `#[repr(C)]` and derives `Pod`/
+/// `Zeroable`, so that types built from it (such as [`AABB`] and, in turn,
+/// [`FlatNode`]) can themselves be made GPU-uploadable.
+///
+/// [`AABB`]: ../aabb/struct.AABB.html
+/// [`FlatNode`]: ../flat_bvh/struct.FlatNode.html
+///
+#[cfg_attr(feature = "bytemuck", repr(C))]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point3 {
+    /// The x coordinate.
+    pub x: f32,
+    /// The y coordinate.
+    pub y: f32,
+    /// The z coordinate.
+    pub z: f32,
+}
+
+/// A displacement in 3D space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector3 {
+    /// The x component.
+    pub x: f32,
+    /// The y component.
+    pub y: f32,
+    /// The z component.
+    pub z: f32,
+}
+
+impl Point3 {
+    /// Creates a new `Point3`.
+    pub fn new(x: f32, y: f32, z: f32) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    /// Component-wise minimum of two points.
+    pub fn min(&self, other: &Point3) -> Point3 {
+        Point3::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+
+    /// Component-wise maximum of two points.
+    pub fn max(&self, other: &Point3) -> Point3 {
+        Point3::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
+
+    /// Returns `false` if any coordinate is NaN or infinite.
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+}
+
+impl Vector3 {
+    /// Creates a new `Vector3`.
+    pub fn new(x: f32, y: f32, z: f32) -> Vector3 {
+        Vector3 { x, y, z }
+    }
+
+    /// The dot product of two vectors.
+    pub fn dot(&self, other: &Vector3) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Component-wise product of two vectors.
+    pub fn component_mul(&self, other: &Vector3) -> Vector3 {
+        Vector3::new(self.x * other.x, self.y * other.y, self.z * other.z)
+    }
+
+    /// Component-wise reciprocal.
+    pub fn reciprocal(&self) -> Vector3 {
+        Vector3::new(1.0 / self.x, 1.0 / self.y, 1.0 / self.z)
+    }
+}
+
+impl Add<Vector3> for Point3 {
+    type Output = Point3;
+    fn add(self, rhs: Vector3) -> Point3 {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub<Point3> for Point3 {
+    type Output = Vector3;
+    fn sub(self, rhs: Point3) -> Vector3 {
+        Vector3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul<f32> for Vector3 {
+    type Output = Vector3;
+    fn mul(self, rhs: f32) -> Vector3 {
+        Vector3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Index<crate::axis::Axis> for Point3 {
+    type Output = f32;
+    fn index(&self, axis: crate::axis::Axis) -> &f32 {
+        match axis {
+            crate::axis::Axis::X => &self.x,
+            crate::axis::Axis::Y => &self.y,
+            crate::axis::Axis::Z => &self.z,
+        }
+    }
+}
+
+impl Index<crate::axis::Axis> for Vector3 {
+    type Output = f32;
+    fn index(&self, axis: crate::axis::Axis) -> &f32 {
+        match axis {
+            crate::axis::Axis::X => &self.x,
+            crate::axis::Axis::Y => &self.y,
+            crate::axis::Axis::Z => &self.z,
+        }
+    }
+}